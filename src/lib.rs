@@ -1,11 +1,15 @@
 #[macro_use]
 extern crate log;
+extern crate nix;
 
-use std::fs::File;
+use std::fmt;
+use std::fs::{self, File};
 use std::io::{Error, ErrorKind, Read, Write};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
+use nix::sys::statvfs::statvfs;
+
 #[test]
 fn test_parser() {
     use std::io::Cursor;
@@ -14,7 +18,7 @@ fn test_parser() {
             fs_spec: "/dev/mapper/xubuntu--vg--ssd-root".to_string(),
             mountpoint: PathBuf::from("/"),
             vfs_type: "ext4".to_string(),
-            mount_options: vec!["noatime".to_string(), "errors=remount-ro".to_string()],
+            mount_options: vec!["noatime".to_string(), "errors=remount-ro".to_string()].into(),
             dump: false,
             fsck_order: 1,
         },
@@ -22,7 +26,7 @@ fn test_parser() {
             fs_spec: "UUID=378f3c86-b21a-4172-832d-e2b3d4bc7511".to_string(),
             mountpoint: PathBuf::from("/boot"),
             vfs_type: "ext2".to_string(),
-            mount_options: vec!["defaults".to_string()],
+            mount_options: vec!["defaults".to_string()].into(),
             dump: false,
             fsck_order: 2,
         },
@@ -30,7 +34,7 @@ fn test_parser() {
             fs_spec: "/dev/mapper/xubuntu--vg--ssd-swap_1".to_string(),
             mountpoint: PathBuf::from("none"),
             vfs_type: "swap".to_string(),
-            mount_options: vec!["sw".to_string()],
+            mount_options: vec!["sw".to_string()].into(),
             dump: false,
             fsck_order: 0,
         },
@@ -38,7 +42,7 @@ fn test_parser() {
             fs_spec: "UUID=be8a49b9-91a3-48df-b91b-20a0b409ba0f".to_string(),
             mountpoint: PathBuf::from("/mnt/raid"),
             vfs_type: "ext4".to_string(),
-            mount_options: vec!["errors=remount-ro".to_string(), "user".to_string()],
+            mount_options: vec!["errors=remount-ro".to_string(), "user".to_string()].into(),
             dump: false,
             fsck_order: 1,
         },
@@ -61,8 +65,60 @@ UUID=be8a49b9-91a3-48df-b91b-20a0b409ba0f /mnt/raid ext4 errors=remount-ro,user
     let bytes = input.as_bytes();
     let mut buff = Cursor::new(bytes);
     let fstab = FsTab::new(&Path::new("/fake"));
-    let results = fstab.parse_entries(&mut buff).unwrap();
-    println!("Result: {:?}", results);
+    let lines = fstab.parse_entries(&mut buff).unwrap();
+    println!("Result: {:?}", lines);
+    let expected_lines = vec![
+        FstabLine::Blank,
+        FstabLine::Comment("# /etc/fstab: static file system information.".to_string()),
+        FstabLine::Comment("#".to_string()),
+        FstabLine::Comment(
+            "# Use 'blkid' to print the universally unique identifier for a".to_string(),
+        ),
+        FstabLine::Comment(
+            "# device; this may be used with UUID= as a more robust way to name devices"
+                .to_string(),
+        ),
+        FstabLine::Comment(
+            "# that works even if disks are added and removed. See fstab(5).".to_string(),
+        ),
+        FstabLine::Comment("#".to_string()),
+        FstabLine::Comment(
+            "# <file system> <mount point>   <type>  <options>       <dump>  <pass>".to_string(),
+        ),
+        FstabLine::Entry(
+            expected_results[0].clone(),
+            Some(
+                "/dev/mapper/xubuntu--vg--ssd-root /               ext4    noatime,errors=remount-ro 0       1"
+                    .to_string(),
+            ),
+        ),
+        FstabLine::Comment("# /boot was on /dev/sda1 during installation".to_string()),
+        FstabLine::Entry(
+            expected_results[1].clone(),
+            Some(
+                "UUID=378f3c86-b21a-4172-832d-e2b3d4bc7511 /boot           ext2    defaults        0       2"
+                    .to_string(),
+            ),
+        ),
+        FstabLine::Entry(
+            expected_results[2].clone(),
+            Some(
+                "/dev/mapper/xubuntu--vg--ssd-swap_1 none            swap    sw              0       0"
+                    .to_string(),
+            ),
+        ),
+        FstabLine::Entry(
+            expected_results[3].clone(),
+            Some(
+                "UUID=be8a49b9-91a3-48df-b91b-20a0b409ba0f /mnt/raid ext4 errors=remount-ro,user 0 1"
+                    .to_string(),
+            ),
+        ),
+        FstabLine::Comment("# tmpfs /tmp tmpfs rw,nosuid,nodev".to_string()),
+    ];
+    assert_eq!(lines, expected_lines);
+
+    let results: Vec<FsEntry> = entries_from_lines(&lines);
     assert_eq!(results, expected_results);
 
     //Modify an entry and then update it and see what the results are
@@ -72,6 +128,323 @@ UUID=be8a49b9-91a3-48df-b91b-20a0b409ba0f /mnt/raid ext4 errors=remount-ro,user
     //println!("Wrote: {}", bytes_written);
 }
 
+#[test]
+fn test_parser_tolerant_fields() {
+    use std::io::Cursor;
+    let input = "tmpfs /tmp tmpfs defaults\n";
+    let bytes = input.as_bytes();
+    let mut buff = Cursor::new(bytes);
+    let fstab = FsTab::new(&Path::new("/fake"));
+    let lines = fstab.parse_entries(&mut buff).unwrap();
+    let results = entries_from_lines(&lines);
+    assert_eq!(
+        results,
+        vec![
+            FsEntry {
+                fs_spec: "tmpfs".to_string(),
+                mountpoint: PathBuf::from("/tmp"),
+                vfs_type: "tmpfs".to_string(),
+                mount_options: vec!["defaults".to_string()].into(),
+                dump: false,
+                fsck_order: 0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_parser_vfstab() {
+    use std::io::Cursor;
+    let input = "#device         device        mount           FS      fsck    mount    mount\n\
+                 #to mount       to fsck       point           type    pass    at boot  options\n\
+                 #\n\
+                 /dev/dsk/c0t0d0s0 /dev/rdsk/c0t0d0s0 /        ufs     1       no       -\n\
+                 /dev/dsk/c0t0d0s7 /dev/rdsk/c0t0d0s7 /home    ufs     2       yes      rw,noatime\n";
+    let bytes = input.as_bytes();
+    let mut buff = Cursor::new(bytes);
+    let fstab = FsTab::with_format(&Path::new("/fake"), FsTabFormat::Vfstab);
+    let lines = fstab.parse_entries(&mut buff).unwrap();
+    let results = entries_from_lines(&lines);
+    assert_eq!(
+        results,
+        vec![
+            FsEntry {
+                fs_spec: "/dev/dsk/c0t0d0s0".to_string(),
+                mountpoint: PathBuf::from("/"),
+                vfs_type: "ufs".to_string(),
+                mount_options: vec!["-".to_string()].into(),
+                dump: false,
+                fsck_order: 1,
+            },
+            FsEntry {
+                fs_spec: "/dev/dsk/c0t0d0s7".to_string(),
+                mountpoint: PathBuf::from("/home"),
+                vfs_type: "ufs".to_string(),
+                mount_options: vec!["rw".to_string(), "noatime".to_string()].into(),
+                dump: false,
+                fsck_order: 2,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_parser_filesystems() {
+    use std::io::Cursor;
+    let input = "* /etc/filesystems\n\
+                 /:\n\
+                 \tdev\t\t= /dev/hd4\n\
+                 \tvfs\t\t= jfs2\n\
+                 \tmount\t\t= automatic\n\
+                 \toptions\t\t= rw\n\
+                 \n\
+                 /home:\n\
+                 \tdev\t\t= /dev/hd1\n\
+                 \tvfs\t\t= jfs2\n\
+                 \tmount\t\t= true\n\
+                 \toptions\t\t= rw,noatime\n";
+    let bytes = input.as_bytes();
+    let mut buff = Cursor::new(bytes);
+    let fstab = FsTab::with_format(&Path::new("/fake"), FsTabFormat::Filesystems);
+    let lines = fstab.parse_entries(&mut buff).unwrap();
+    let results = entries_from_lines(&lines);
+    assert_eq!(
+        results,
+        vec![
+            FsEntry {
+                fs_spec: "/dev/hd4".to_string(),
+                mountpoint: PathBuf::from("/"),
+                vfs_type: "jfs2".to_string(),
+                mount_options: vec!["rw".to_string()].into(),
+                dump: false,
+                fsck_order: 0,
+            },
+            FsEntry {
+                fs_spec: "/dev/hd1".to_string(),
+                mountpoint: PathBuf::from("/home"),
+                vfs_type: "jfs2".to_string(),
+                mount_options: vec!["rw".to_string(), "noatime".to_string()].into(),
+                dump: false,
+                fsck_order: 0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_save_filesystems_preserves_blank_line_order() {
+    use std::io::Cursor;
+    let input = "* /etc/filesystems\n\
+                 /:\n\
+                 \tdev\t\t= /dev/hd4\n\
+                 \tvfs\t\t= jfs2\n\
+                 \tmount\t\t= automatic\n\
+                 \toptions\t\t= rw\n\
+                 \n\
+                 /home:\n\
+                 \tdev\t\t= /dev/hd1\n\
+                 \tvfs\t\t= jfs2\n\
+                 \tmount\t\t= true\n\
+                 \toptions\t\t= rw,noatime\n\
+                 \n\
+                 /tmp:\n\
+                 \tdev\t\t= /dev/hd3\n\
+                 \tvfs\t\t= jfs2\n\
+                 \tmount\t\t= true\n\
+                 \toptions\t\t= rw\n";
+    let mut buff = Cursor::new(input.as_bytes());
+    let path = std::env::temp_dir().join(format!(
+        "fstab-filesystems-blank-test-{}",
+        std::process::id()
+    ));
+    let fstab = FsTab::with_format(&path, FsTabFormat::Filesystems);
+    let lines = fstab.parse_entries(&mut buff).unwrap();
+    fstab.save_fstab(&lines).unwrap();
+    let contents = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(contents, input);
+}
+
+#[test]
+fn test_mount_options_helpers() {
+    let opts: MountOptions = vec![
+        "noatime".to_string(),
+        "subvol=@home".to_string(),
+        "uid=1000".to_string(),
+        "gid=users".to_string(),
+    ].into();
+    assert!(opts.has_flag("noatime"));
+    assert!(!opts.has_flag("ro"));
+    assert_eq!(opts.get("subvol"), Some("@home"));
+    assert_eq!(opts.get("uid"), Some("1000"));
+    assert_eq!(opts.get("gid"), Some("users"));
+    assert_eq!(opts.get("missing"), None);
+    assert!(!opts.is_bind());
+
+    let bind_opts: MountOptions = vec!["bind".to_string()].into();
+    assert!(bind_opts.is_bind());
+
+    let subvolid_opts: MountOptions = vec!["subvolid=256".to_string()].into();
+    assert_eq!(subvolid_opts.get("subvolid"), Some("256"));
+}
+
+#[test]
+fn test_save_preserves_untouched_entries() {
+    let path = std::env::temp_dir().join(format!("fstab-save-test-{}", std::process::id()));
+    fs::write(&path, "tmpfs /tmp tmpfs defaults\n").unwrap();
+    let fstab = FsTab::new(&path);
+
+    fstab
+        .add_entry(FsEntry {
+            fs_spec: "proc".to_string(),
+            mountpoint: PathBuf::from("/proc"),
+            vfs_type: "proc".to_string(),
+            mount_options: vec!["defaults".to_string()].into(),
+            dump: false,
+            fsck_order: 0,
+        })
+        .unwrap();
+    fstab.remove_entry("proc").unwrap();
+
+    let contents = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(contents, "tmpfs /tmp tmpfs defaults\n");
+}
+
+#[test]
+fn test_save_preserves_vfstab_and_filesystems_columns() {
+    let path = std::env::temp_dir().join(format!("fstab-vfstab-save-test-{}", std::process::id()));
+    fs::write(
+        &path,
+        "/dev/dsk/c0t0d0s7 /dev/rdsk/c0t0d0s7 /home    ufs     2       no       rw,noatime\n",
+    )
+    .unwrap();
+    let fstab = FsTab::with_format(&path, FsTabFormat::Vfstab);
+
+    fstab
+        .add_entry(FsEntry {
+            fs_spec: "swap".to_string(),
+            mountpoint: PathBuf::from("none"),
+            vfs_type: "swap".to_string(),
+            mount_options: vec!["defaults".to_string()].into(),
+            dump: false,
+            fsck_order: 0,
+        })
+        .unwrap();
+    fstab.remove_entry("swap").unwrap();
+
+    let contents = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(
+        contents.contains(" no "),
+        "untouched vfstab entry lost its mount_at_boot column: {}",
+        contents
+    );
+
+    let path = std::env::temp_dir().join(format!(
+        "fstab-filesystems-save-test-{}",
+        std::process::id()
+    ));
+    fs::write(
+        &path,
+        "/home:\n\tdev\t\t= /dev/hd1\n\tvfs\t\t= jfs2\n\tmount\t\t= automatic\n\toptions\t\t= rw,noatime\n",
+    )
+    .unwrap();
+    let fstab = FsTab::with_format(&path, FsTabFormat::Filesystems);
+
+    fstab
+        .add_entry(FsEntry {
+            fs_spec: "/dev/hd3".to_string(),
+            mountpoint: PathBuf::from("/tmp"),
+            vfs_type: "jfs2".to_string(),
+            mount_options: vec!["rw".to_string()].into(),
+            dump: false,
+            fsck_order: 0,
+        })
+        .unwrap();
+    fstab.remove_entry("/dev/hd3").unwrap();
+
+    let contents = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(
+        contents.contains("mount\t\t= automatic"),
+        "untouched /etc/filesystems stanza lost its mount = value: {}",
+        contents
+    );
+}
+
+#[test]
+fn test_usage_requires_real_mount() {
+    let dir = std::env::temp_dir().join(format!("fstab-usage-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let entry = FsEntry {
+        fs_spec: "none".to_string(),
+        mountpoint: dir.clone(),
+        vfs_type: "tmpfs".to_string(),
+        mount_options: vec!["defaults".to_string()].into(),
+        dump: false,
+        fsck_order: 0,
+    };
+    let result = entry.usage();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_unescape_mountinfo_field() {
+    assert_eq!(unescape_mountinfo_field("/mnt/my\\040drive"), "/mnt/my drive");
+    assert_eq!(unescape_mountinfo_field("/mnt/plain"), "/mnt/plain");
+}
+
+#[test]
+fn test_resolve_spec_under() {
+    let base = std::env::temp_dir().join(format!("fstab-resolve-spec-test-{}", std::process::id()));
+    let by_uuid = base.join("by-uuid");
+    let by_label = base.join("by-label");
+    fs::create_dir_all(&by_uuid).unwrap();
+    fs::create_dir_all(&by_label).unwrap();
+
+    let device = base.join("sda1");
+    fs::write(&device, b"").unwrap();
+    std::os::unix::fs::symlink(&device, by_uuid.join("1111-2222")).unwrap();
+    std::os::unix::fs::symlink(&device, by_label.join("root")).unwrap();
+    let expected = device.canonicalize().unwrap();
+
+    let resolved_uuid = resolve_spec_under("UUID=1111-2222", &base).unwrap();
+    let resolved_label = resolve_spec_under("LABEL=root", &base).unwrap();
+    let resolved_plain = resolve_spec_under(device.to_str().unwrap(), &base).unwrap();
+    let missing = resolve_spec_under("UUID=does-not-exist", &base);
+
+    fs::remove_dir_all(&base).unwrap();
+
+    assert_eq!(resolved_uuid, expected);
+    assert_eq!(resolved_label, expected);
+    assert_eq!(resolved_plain, expected);
+    assert!(missing.is_err());
+}
+
+#[test]
+fn test_uuid_spec_for_under() {
+    let base = std::env::temp_dir().join(format!("fstab-uuid-spec-test-{}", std::process::id()));
+    let by_uuid = base.join("by-uuid");
+    fs::create_dir_all(&by_uuid).unwrap();
+
+    let device = base.join("sda1");
+    fs::write(&device, b"").unwrap();
+    std::os::unix::fs::symlink(&device, by_uuid.join("aaaa-bbbb")).unwrap();
+
+    let found = uuid_spec_for_under(device.to_str().unwrap(), &base);
+
+    let unlinked_device = base.join("sdb1");
+    fs::write(&unlinked_device, b"").unwrap();
+    let not_found = uuid_spec_for_under(unlinked_device.to_str().unwrap(), &base);
+
+    fs::remove_dir_all(&base).unwrap();
+
+    assert_eq!(found.unwrap(), "UUID=aaaa-bbbb");
+    assert!(not_found.is_err());
+}
+
 /// For help with what these fields mean consult: `man fstab` on linux.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FsEntry {
@@ -82,7 +455,7 @@ pub struct FsEntry {
     /// Which filesystem type it is
     pub vfs_type: String,
     /// Mount options to use
-    pub mount_options: Vec<String>,
+    pub mount_options: MountOptions,
     /// This field is used by dump(8) to determine which filesystems need to be dumped
     pub dump: bool,
     /// This field is used by fsck(8) to determine the order in which filesystem checks
@@ -90,93 +463,520 @@ pub struct FsEntry {
     pub fsck_order: u16,
 }
 
+impl FsEntry {
+    /// Query how full this entry's mountpoint currently is, via `statvfs(2)`.
+    /// Returns `None` for entries with no real mountpoint to query, such as a
+    /// swap entry whose mountpoint is `none`. Returns an error if the
+    /// mountpoint is not currently mounted.
+    pub fn usage(&self) -> Result<Option<DiskUsage>, Error> {
+        let mountpoint = self.mountpoint.to_string_lossy();
+        if mountpoint.is_empty() || mountpoint == "none" {
+            return Ok(None);
+        }
+        if !is_mount_point(&self.mountpoint)? {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!("{} is not currently mounted", self.mountpoint.display()),
+            ));
+        }
+        let stats = statvfs(&self.mountpoint).map_err(|e| Error::new(ErrorKind::Other, e))?;
+        Ok(Some(DiskUsage {
+            total_bytes: stats.block_size() as u64 * stats.blocks() as u64,
+            available_bytes: stats.block_size() as u64 * stats.blocks_available() as u64,
+        }))
+    }
+
+    /// Resolve this entry's `fs_spec` to the block device it currently
+    /// refers to. Specs written as `UUID=`, `LABEL=`, or `PARTUUID=` are
+    /// looked up under `/dev/disk/by-*` before canonicalizing; a spec that
+    /// is already a path is canonicalized directly.
+    pub fn resolve_device(&self) -> Result<PathBuf, Error> {
+        resolve_spec(&self.fs_spec)
+    }
+
+    /// Build a `UUID=`-based spec for this entry's `fs_spec` by finding the
+    /// `/dev/disk/by-uuid/` symlink that points at the same device, so the
+    /// entry can survive the underlying device node being renamed. The
+    /// entry itself is not modified; the new spec is returned.
+    pub fn to_uuid_spec(&self) -> Result<String, Error> {
+        uuid_spec_for(&self.fs_spec)
+    }
+}
+
+/// Resolve a `UUID=`/`LABEL=`/`PARTUUID=` fstab spec (or plain device path)
+/// to its canonical block device path.
+fn resolve_spec(spec: &str) -> Result<PathBuf, Error> {
+    resolve_spec_under(spec, Path::new("/dev/disk"))
+}
+
+/// Like [`resolve_spec`], but looks up `by-uuid`/`by-label`/`by-partuuid`
+/// under `by_disk_dir` instead of the hardcoded `/dev/disk`, so tests can
+/// point it at a tempdir of fake symlinks.
+fn resolve_spec_under(spec: &str, by_disk_dir: &Path) -> Result<PathBuf, Error> {
+    let path = if let Some(uuid) = spec.strip_prefix("UUID=") {
+        by_disk_dir.join("by-uuid").join(uuid)
+    } else if let Some(label) = spec.strip_prefix("LABEL=") {
+        by_disk_dir.join("by-label").join(label)
+    } else if let Some(partuuid) = spec.strip_prefix("PARTUUID=") {
+        by_disk_dir.join("by-partuuid").join(partuuid)
+    } else {
+        PathBuf::from(spec)
+    };
+    path.canonicalize()
+}
+
+/// Find the `by-uuid/` entry that resolves to the same device as `spec` and
+/// return it as a `UUID=...` spec string.
+fn uuid_spec_for(spec: &str) -> Result<String, Error> {
+    uuid_spec_for_under(spec, Path::new("/dev/disk"))
+}
+
+/// Like [`uuid_spec_for`], but looks up `by-uuid` under `by_disk_dir` instead
+/// of the hardcoded `/dev/disk`, so tests can point it at a tempdir of fake
+/// symlinks.
+fn uuid_spec_for_under(spec: &str, by_disk_dir: &Path) -> Result<String, Error> {
+    let target = resolve_spec_under(spec, by_disk_dir)?;
+    let by_uuid_dir = by_disk_dir.join("by-uuid");
+    for entry in fs::read_dir(&by_uuid_dir)? {
+        let entry = entry?;
+        if entry.path().canonicalize()? == target {
+            if let Some(uuid) = entry.file_name().to_str() {
+                return Ok(format!("UUID={}", uuid));
+            }
+        }
+    }
+    Err(Error::new(
+        ErrorKind::NotFound,
+        format!("No UUID symlink under {} points at {}", by_uuid_dir.display(), spec),
+    ))
+}
+
+/// The mount options column of an fstab entry, split into bare flags
+/// (`noatime`, `ro`, `user`) and `key=value` options (`uid=1000`,
+/// `subvol=@home`), while preserving their original ordering so
+/// `save_fstab` can round-trip them back to an identical comma-joined
+/// string.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MountOptions {
+    raw: Vec<String>,
+}
+
+impl MountOptions {
+    /// Parse a comma-separated mount_options column, e.g. `noatime,errors=remount-ro`.
+    fn parse(options: &str) -> Self {
+        MountOptions::from(options.split(",").map(|s| s.to_string()).collect::<Vec<String>>())
+    }
+
+    /// Whether `flag` is present as a bare option, i.e. one with no `=`.
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.raw.iter().any(|o| o == flag)
+    }
+
+    /// The value of a `key=value` option, if present.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.raw.iter().filter_map(|o| {
+            let mut parts = o.splitn(2, "=");
+            let k = parts.next()?;
+            let v = parts.next()?;
+            if k == key { Some(v) } else { None }
+        }).next()
+    }
+
+    /// Whether these options mark a bind mount (`mount --bind`).
+    pub fn is_bind(&self) -> bool {
+        self.has_flag("bind")
+    }
+}
+
+impl From<Vec<String>> for MountOptions {
+    fn from(raw: Vec<String>) -> Self {
+        MountOptions { raw: raw }
+    }
+}
+
+#[allow(clippy::from_over_into)]
+impl Into<Vec<String>> for MountOptions {
+    fn into(self) -> Vec<String> {
+        self.raw
+    }
+}
+
+impl fmt::Display for MountOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.raw.join(","))
+    }
+}
+
+/// Whether `path` is currently a mount point, per `/proc/self/mountinfo`.
+/// Unlike comparing `st_dev` against the parent directory, this also
+/// recognizes bind mounts (`mount --bind`), which keep the same device id
+/// as their target but still get their own `mountinfo` entry.
+fn is_mount_point(path: &Path) -> Result<bool, Error> {
+    let canonical = path.canonicalize()?;
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo")?;
+    for line in mountinfo.lines() {
+        if let Some(raw_mountpoint) = line.split_whitespace().nth(4) {
+            if unescape_mountinfo_field(raw_mountpoint) == canonical.to_string_lossy() {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Undo the octal `\NNN` escaping `/proc/self/mountinfo` uses for spaces,
+/// tabs, and other special characters in its path fields.
+fn unescape_mountinfo_field(field: &str) -> String {
+    let mut result = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        let octal: String = chars.by_ref().take(3).collect();
+        match u8::from_str_radix(&octal, 8) {
+            Ok(byte) => result.push(byte as char),
+            Err(_) => {
+                result.push(c);
+                result.push_str(&octal);
+            }
+        }
+    }
+    result
+}
+
+/// Capacity information for a mounted filesystem, as reported by `statvfs(2)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DiskUsage {
+    /// Total size of the filesystem, in bytes
+    pub total_bytes: u64,
+    /// Space available to unprivileged users, in bytes
+    pub available_bytes: u64,
+}
+
+/// A single line of an fstab-style file (for `/etc/filesystems`, an entry
+/// covers its whole stanza), preserved verbatim unless it is an entry that
+/// the caller chose to add or remove.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum FstabLine {
+    /// A comment line, stored including its leading `#` (`*` in
+    /// `/etc/filesystems`).
+    Comment(String),
+    /// An empty line.
+    Blank,
+    /// A parsed mount entry, along with the exact on-disk text it was parsed
+    /// from, if any. `save_fstab` re-emits that text verbatim for entries
+    /// the caller never touched, rather than reconstructing the line (and
+    /// losing whatever columns `FsEntry` doesn't model) from scratch.
+    /// Entries that were freshly added or replaced carry `None` and are
+    /// rendered via `format_entry`.
+    Entry(FsEntry, Option<String>),
+}
+
+/// Pull just the `FsEntry` values out of a parsed line list, in file order.
+fn entries_from_lines(lines: &[FstabLine]) -> Vec<FsEntry> {
+    lines
+        .iter()
+        .filter_map(|line| match *line {
+            FstabLine::Entry(ref e, _) => Some(e.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Which on-disk convention a mount table follows. Linux, SVR4, and AIX each
+/// lay the same basic information out differently.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FsTabFormat {
+    /// Linux `/etc/fstab`: six whitespace separated columns.
+    Fstab,
+    /// SVR4 `/etc/vfstab`: `device device_to_fsck mount_point FS_type fsck_pass mount_at_boot mount_options`.
+    Vfstab,
+    /// AIX `/etc/filesystems`: `/mount/point:` stanzas of indented `key = value` lines.
+    Filesystems,
+}
+
+impl Default for FsTabFormat {
+    fn default() -> Self {
+        FsTabFormat::Fstab
+    }
+}
+
 #[derive(Debug)]
 pub struct FsTab {
     location: PathBuf,
+    format: FsTabFormat,
 }
 
 impl Default for FsTab {
     fn default() -> Self {
-        FsTab { location: PathBuf::from("/etc/fstab") }
+        FsTab {
+            location: PathBuf::from("/etc/fstab"),
+            format: FsTabFormat::default(),
+        }
     }
 }
 
 impl FsTab {
     pub fn new(fstab: &Path) -> Self {
-        FsTab { location: fstab.to_path_buf() }
+        FsTab {
+            location: fstab.to_path_buf(),
+            format: FsTabFormat::Fstab,
+        }
+    }
+
+    /// Construct a `FsTab` for a mount table that isn't a Linux `/etc/fstab`,
+    /// such as SVR4's `/etc/vfstab` or AIX's `/etc/filesystems`.
+    pub fn with_format(location: &Path, format: FsTabFormat) -> Self {
+        FsTab {
+            location: location.to_path_buf(),
+            format: format,
+        }
     }
 
     /// Takes the location to the fstab and parses it.  On linux variants
     /// this is usually /etc/fstab.  On SVR4 systems store block devices and
     /// mount point information in /etc/vfstab file. AIX stores block device
-    /// and mount points information in /etc/filesystems file.
+    /// and mount points information in /etc/filesystems file.  Use
+    /// `with_format` to point at one of the latter two.
     pub fn get_entries(&self) -> Result<Vec<FsEntry>, Error> {
         let mut file = File::open(&self.location)?;
-        let entries = self.parse_entries(&mut file)?;
-        Ok(entries)
+        let lines = self.parse_entries(&mut file)?;
+        Ok(entries_from_lines(&lines))
     }
 
-    fn parse_entries<T: Read>(&self, file: &mut T) -> Result<Vec<FsEntry>, Error> {
-        let mut entries: Vec<FsEntry> = Vec::new();
+    fn parse_entries<T: Read>(&self, file: &mut T) -> Result<Vec<FstabLine>, Error> {
         let mut contents = String::new();
         file.read_to_string(&mut contents)?;
+        match self.format {
+            FsTabFormat::Fstab => self.parse_fstab(&contents),
+            FsTabFormat::Vfstab => self.parse_vfstab(&contents),
+            FsTabFormat::Filesystems => self.parse_filesystems(&contents),
+        }
+    }
 
+    /// Parse the Linux six-column `fstab(5)` layout.
+    fn parse_fstab(&self, contents: &str) -> Result<Vec<FstabLine>, Error> {
+        let mut lines: Vec<FstabLine> = Vec::new();
         for line in contents.lines() {
+            if line.trim().is_empty() {
+                lines.push(FstabLine::Blank);
+                continue;
+            }
             if line.starts_with("#") {
-                trace!("Skipping commented line: {}", line);
+                trace!("Preserving commented line: {}", line);
+                lines.push(FstabLine::Comment(line.to_string()));
                 continue;
             }
             let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() != 6 {
+            // `man fstab` specifies dump and fsck_order both default to 0 when
+            // the trailing columns are omitted, so accept 4- and 5-field lines.
+            if parts.len() < 4 {
                 debug!("Unknown fstab entry: {}", line);
                 continue;
             }
-            let fsck_order = u16::from_str(parts[5]).map_err(|e| {
-                Error::new(ErrorKind::InvalidInput, e)
-            })?;
-            entries.push(FsEntry {
-                fs_spec: parts[0].to_string(),
-                mountpoint: PathBuf::from(parts[1]),
-                vfs_type: parts[2].to_string(),
-                mount_options: parts[3].split(",").map(|s| s.to_string()).collect(),
-                dump: if parts[4] == "0" { false } else { true },
-                fsck_order: fsck_order,
-            })
+            let dump = match parts.get(4) {
+                Some(field) => *field != "0",
+                None => false,
+            };
+            let fsck_order = match parts.get(5) {
+                Some(field) => u16::from_str(field).map_err(|e| {
+                    Error::new(ErrorKind::InvalidInput, e)
+                })?,
+                None => 0,
+            };
+            lines.push(FstabLine::Entry(
+                FsEntry {
+                    fs_spec: parts[0].to_string(),
+                    mountpoint: PathBuf::from(parts[1]),
+                    vfs_type: parts[2].to_string(),
+                    mount_options: MountOptions::parse(parts[3]),
+                    dump: dump,
+                    fsck_order: fsck_order,
+                },
+                Some(line.to_string()),
+            ))
         }
-        Ok(entries)
+        Ok(lines)
     }
 
-    fn save_fstab(&self, entries: &Vec<FsEntry>) -> Result<usize, Error> {
+    /// Parse the SVR4 seven-column `vfstab(4)` layout:
+    /// `device device_to_fsck mount_point FS_type fsck_pass mount_at_boot mount_options`.
+    fn parse_vfstab(&self, contents: &str) -> Result<Vec<FstabLine>, Error> {
+        let mut lines: Vec<FstabLine> = Vec::new();
+        for line in contents.lines() {
+            if line.trim().is_empty() {
+                lines.push(FstabLine::Blank);
+                continue;
+            }
+            if line.starts_with("#") {
+                trace!("Preserving commented line: {}", line);
+                lines.push(FstabLine::Comment(line.to_string()));
+                continue;
+            }
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() != 7 {
+                debug!("Unknown vfstab entry: {}", line);
+                continue;
+            }
+            let fsck_order = if parts[4] == "-" {
+                0
+            } else {
+                u16::from_str(parts[4]).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?
+            };
+            lines.push(FstabLine::Entry(
+                FsEntry {
+                    fs_spec: parts[0].to_string(),
+                    mountpoint: PathBuf::from(parts[2]),
+                    vfs_type: parts[3].to_string(),
+                    mount_options: MountOptions::parse(parts[6]),
+                    dump: false,
+                    fsck_order: fsck_order,
+                },
+                Some(line.to_string()),
+            ))
+        }
+        Ok(lines)
+    }
+
+    /// Parse the AIX `/mount/point:` stanza layout used by `/etc/filesystems`.
+    fn parse_filesystems(&self, contents: &str) -> Result<Vec<FstabLine>, Error> {
+        let mut lines: Vec<FstabLine> = Vec::new();
+        #[allow(clippy::type_complexity)]
+        let mut stanza: Option<(String, String, String, Vec<String>, Vec<String>)> = None;
+
+        macro_rules! flush_stanza {
+            () => {
+                if let Some((mountpoint, fs_spec, vfs_type, mount_options, raw_lines)) =
+                    stanza.take()
+                {
+                    lines.push(FstabLine::Entry(
+                        FsEntry {
+                            fs_spec: fs_spec,
+                            mountpoint: PathBuf::from(mountpoint),
+                            vfs_type: vfs_type,
+                            mount_options: MountOptions::from(mount_options),
+                            dump: false,
+                            fsck_order: 0,
+                        },
+                        Some(raw_lines.join("\n")),
+                    ));
+                }
+            };
+        }
+
+        for raw_line in contents.lines() {
+            let line = raw_line.trim();
+            if line.is_empty() {
+                // A blank line ends whatever stanza came before it, so flush
+                // it first; otherwise this blank line would be hoisted ahead
+                // of the stanza's entry when that entry is finally flushed by
+                // the *next* header (or EOF).
+                flush_stanza!();
+                lines.push(FstabLine::Blank);
+                continue;
+            }
+            if line.starts_with("*") {
+                lines.push(FstabLine::Comment(raw_line.to_string()));
+                continue;
+            }
+            if let Some(mountpoint) = line.strip_suffix(":") {
+                flush_stanza!();
+                stanza = Some((
+                    mountpoint.to_string(),
+                    String::new(),
+                    String::new(),
+                    Vec::new(),
+                    vec![raw_line.to_string()],
+                ));
+                continue;
+            }
+            if let Some(eq) = line.find('=') {
+                let key = line[..eq].trim();
+                let value = line[eq + 1..].trim();
+                if let Some((_, ref mut fs_spec, ref mut vfs_type, ref mut mount_options, ref mut raw_lines)) =
+                    stanza
+                {
+                    raw_lines.push(raw_line.to_string());
+                    match key {
+                        "dev" => *fs_spec = value.to_string(),
+                        "vfs" => *vfs_type = value.to_string(),
+                        "options" => {
+                            *mount_options = value.split(",").map(|s| s.to_string()).collect()
+                        }
+                        _ => debug!("Ignoring unknown /etc/filesystems key: {}", key),
+                    }
+                }
+            }
+        }
+        flush_stanza!();
+        Ok(lines)
+    }
+
+    fn save_fstab(&self, lines: &Vec<FstabLine>) -> Result<usize, Error> {
         let mut file = File::create(&self.location)?;
         let mut bytes_written: usize = 0;
-        for entry in entries {
-            bytes_written += file.write(&format!(
+        for line in lines {
+            let text = match *line {
+                FstabLine::Comment(ref text) => format!("{}\n", text),
+                FstabLine::Blank => "\n".to_string(),
+                FstabLine::Entry(_, Some(ref raw)) => format!("{}\n", raw),
+                FstabLine::Entry(ref entry, None) => self.format_entry(entry),
+            };
+            bytes_written += file.write(text.as_bytes())?;
+        }
+        file.flush()?;
+        debug!("Wrote {} bytes to fstab", bytes_written);
+        Ok(bytes_written)
+    }
+
+    /// Render a single entry according to `self.format`.
+    fn format_entry(&self, entry: &FsEntry) -> String {
+        match self.format {
+            FsTabFormat::Fstab => format!(
                 "{spec} {mount} {vfs} {options} {dump} {fsck}\n",
                 spec = entry.fs_spec,
                 mount = entry.mountpoint.display(),
                 vfs = entry.vfs_type,
-                options = entry.mount_options.join(","),
+                options = entry.mount_options,
                 dump = if entry.dump { "1" } else { "0" },
                 fsck = entry.fsck_order
-            ).as_bytes())?;
+            ),
+            FsTabFormat::Vfstab => format!(
+                "{spec} - {mount} {vfs} {fsck} yes {options}\n",
+                spec = entry.fs_spec,
+                mount = entry.mountpoint.display(),
+                vfs = entry.vfs_type,
+                fsck = entry.fsck_order,
+                options = entry.mount_options
+            ),
+            FsTabFormat::Filesystems => format!(
+                "{mount}:\n\tdev\t\t= {spec}\n\tvfs\t\t= {vfs}\n\tmount\t\t= true\n\toptions\t\t= {options}\n",
+                mount = entry.mountpoint.display(),
+                spec = entry.fs_spec,
+                vfs = entry.vfs_type,
+                options = entry.mount_options
+            ),
         }
-        file.flush()?;
-        debug!("Wrote {} bytes to fstab", bytes_written);
-        Ok(bytes_written)
     }
 
     /// Add a new entry to the fstab.  If the fstab previously did not contain this entry
     /// then true is returned.  Otherwise it will return false indicating it has been updated
     pub fn add_entry(&self, entry: FsEntry) -> Result<bool, Error> {
-        let mut entries = self.get_entries()?;
+        let mut file = File::open(&self.location)?;
+        let mut lines = self.parse_entries(&mut file)?;
 
-        let position = entries.iter().position(|e| e == &entry);
+        let position = lines.iter().position(|l| match *l {
+            FstabLine::Entry(ref e, _) => e == &entry,
+            _ => false,
+        });
         if let Some(pos) = position {
             debug!("Removing {} from fstab entries", pos);
-            entries.remove(pos);
+            lines.remove(pos);
         }
-        entries.push(entry);
-        self.save_fstab(&mut entries)?;
+        lines.push(FstabLine::Entry(entry, None));
+        self.save_fstab(&lines)?;
 
         match position {
             Some(_) => Ok(false),
@@ -186,36 +986,88 @@ impl FsTab {
 
     /// Bulk add a new entries to the fstab.
     pub fn add_entries(&self, entries: Vec<FsEntry>) -> Result<(), Error> {
-        let mut existing_entries = self.get_entries()?;
+        let mut file = File::open(&self.location)?;
+        let mut lines = self.parse_entries(&mut file)?;
         for new_entry in entries {
-            match existing_entries.contains(&new_entry) {
-                false => existing_entries.push(new_entry),
-                true => {
-                    // The old entries contain this so lets update it
-                    let position = existing_entries
-                        .iter()
-                        .position(|e| e == &new_entry)
-                        .unwrap();
-                    existing_entries.remove(position);
-                    existing_entries.push(new_entry);
-                }
+            let position = lines.iter().position(|l| match *l {
+                FstabLine::Entry(ref e, _) => e == &new_entry,
+                _ => false,
+            });
+            if let Some(pos) = position {
+                // The old entries contain this so lets update it
+                lines.remove(pos);
             }
+            lines.push(FstabLine::Entry(new_entry, None));
         }
-        self.save_fstab(&mut existing_entries)?;
+        self.save_fstab(&lines)?;
         Ok(())
     }
 
     /// Remove the fstab entry that corresponds to the spec given.  IE: first fields match
     /// Returns true if the value was present in the fstab.
     pub fn remove_entry(&self, spec: &str) -> Result<bool, Error> {
-        let mut entries = self.get_entries()?;
-        let position = entries.iter().position(|e| e.fs_spec == spec);
+        let mut file = File::open(&self.location)?;
+        let mut lines = self.parse_entries(&mut file)?;
+        let position = lines.iter().position(|l| match *l {
+            FstabLine::Entry(ref e, _) => e.fs_spec == spec,
+            _ => false,
+        });
 
         match position {
             Some(pos) => {
                 debug!("Removing {} from fstab entries", pos);
-                entries.remove(pos);
-                self.save_fstab(&mut entries)?;
+                lines.remove(pos);
+                self.save_fstab(&lines)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Find the entry mounted at `mountpoint`, if any.
+    pub fn find_by_mountpoint(&self, mountpoint: &Path) -> Result<Option<FsEntry>, Error> {
+        let entries = self.get_entries()?;
+        Ok(entries.into_iter().find(|e| e.mountpoint == mountpoint))
+    }
+
+    /// Find the entry whose `fs_spec` matches, if any.
+    pub fn find_by_spec(&self, spec: &str) -> Result<Option<FsEntry>, Error> {
+        let entries = self.get_entries()?;
+        Ok(entries.into_iter().find(|e| e.fs_spec == spec))
+    }
+
+    /// Remove every entry for which `keep` returns false, leaving comments,
+    /// blank lines, and the remaining entries' order untouched.
+    pub fn retain<F>(&self, mut keep: F) -> Result<(), Error>
+    where
+        F: FnMut(&FsEntry) -> bool,
+    {
+        let mut file = File::open(&self.location)?;
+        let mut lines = self.parse_entries(&mut file)?;
+        lines.retain(|l| match *l {
+            FstabLine::Entry(ref e, _) => keep(e),
+            _ => true,
+        });
+        self.save_fstab(&lines)
+            .map(|_| ())
+    }
+
+    /// Replace the entry mounted at `mountpoint` with `entry` in place,
+    /// preserving its position in the file rather than removing it and
+    /// appending the replacement at the end. Returns true if an entry was
+    /// found and replaced.
+    pub fn replace_entry(&self, mountpoint: &Path, entry: FsEntry) -> Result<bool, Error> {
+        let mut file = File::open(&self.location)?;
+        let mut lines = self.parse_entries(&mut file)?;
+        let position = lines.iter().position(|l| match *l {
+            FstabLine::Entry(ref e, _) => e.mountpoint == mountpoint,
+            _ => false,
+        });
+
+        match position {
+            Some(pos) => {
+                lines[pos] = FstabLine::Entry(entry, None);
+                self.save_fstab(&lines)?;
                 Ok(true)
             }
             None => Ok(false),